@@ -0,0 +1,172 @@
+use core::cell::UnsafeCell;
+use core::fmt;
+use core::mem::MaybeUninit;
+
+use crate::CallOnce;
+
+/// A cell that can be written to only once, guarded by a [`CallOnce`].
+///
+/// This is a `no_std` alternative to `std::sync::OnceLock` built on top of [`CallOnce`]: setting
+/// the value is lock-free, and once set, the value can be read by any number of threads.
+///
+/// # Examples
+///
+/// ```
+/// use call_once::CallOnceCell;
+///
+/// static CELL: CallOnceCell<u32> = CallOnceCell::new();
+///
+/// assert_eq!(CELL.get(), None);
+/// assert_eq!(CELL.set(42), Ok(()));
+/// assert_eq!(CELL.set(7), Err(7));
+/// assert_eq!(CELL.get(), Some(&42));
+/// ```
+pub struct CallOnceCell<T> {
+    call_once: CallOnce,
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+// SAFETY: a shared `CallOnceCell<T>` hands out `&T` to any number of threads at once, which
+// requires `T: Sync`, and the value may be set on one thread and dropped on another, which
+// requires `T: Send`. These are the same bounds as `std::sync::OnceLock<T>`.
+unsafe impl<T: Send + Sync> Sync for CallOnceCell<T> {}
+
+impl<T> CallOnceCell<T> {
+    /// Creates a new, uninitialized `CallOnceCell`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use call_once::CallOnceCell;
+    ///
+    /// let cell = CallOnceCell::<u32>::new();
+    /// ```
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            call_once: CallOnce::new(),
+            value: UnsafeCell::new(MaybeUninit::uninit()),
+        }
+    }
+
+    /// Sets the contents of this cell to `value`.
+    ///
+    /// Only the first call returns `Ok`. All subsequent calls return `Err` with the value that
+    /// was rejected, leaving the cell's contents unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use call_once::CallOnceCell;
+    ///
+    /// let cell = CallOnceCell::new();
+    ///
+    /// assert_eq!(cell.set(1), Ok(()));
+    /// assert_eq!(cell.set(2), Err(2));
+    /// assert_eq!(cell.get(), Some(&1));
+    /// ```
+    #[inline]
+    pub fn set(&self, value: T) -> Result<(), T> {
+        let mut value = Some(value);
+        match self.call_once.call_once_with(|| {
+            // SAFETY: we just won the race to initialize the cell, so we have exclusive access
+            // to the value and no other thread can be reading it yet. `call_once_with` only
+            // releases the `COMPLETE` state after this closure returns, so the write
+            // happens-before any `Acquire` load that observes the cell as initialized.
+            unsafe {
+                (*self.value.get()).write(value.take().unwrap());
+            }
+        }) {
+            Ok(()) => Ok(()),
+            Err(_) => Err(value.unwrap()),
+        }
+    }
+
+    /// Returns a reference to the contents of this cell, if it has been set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use call_once::CallOnceCell;
+    ///
+    /// let cell = CallOnceCell::new();
+    /// assert_eq!(cell.get(), None);
+    ///
+    /// cell.set(42).unwrap();
+    /// assert_eq!(cell.get(), Some(&42));
+    /// ```
+    #[inline]
+    pub fn get(&self) -> Option<&T> {
+        if self.call_once.was_called() {
+            // SAFETY: `was_called` returning `true` means the winning `set`/`get_or_init` call
+            // has finished writing the value and released the `COMPLETE` state through
+            // `call_once_with`, and the `Acquire` load in `was_called` synchronizes with that
+            // release, so the write is visible here.
+            Some(unsafe { (*self.value.get()).assume_init_ref() })
+        } else {
+            None
+        }
+    }
+
+    /// Returns a reference to the contents of this cell, initializing it with `f` if it is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use call_once::CallOnceCell;
+    ///
+    /// let cell = CallOnceCell::new();
+    ///
+    /// assert_eq!(cell.get_or_init(|| 42), &42);
+    /// assert_eq!(cell.get_or_init(|| 7), &42);
+    /// ```
+    #[inline]
+    pub fn get_or_init(&self, f: impl FnOnce() -> T) -> &T {
+        let mut f = Some(f);
+        loop {
+            let claimed = self.call_once.call_once_with(|| {
+                // SAFETY: see `set`.
+                unsafe {
+                    (*self.value.get()).write(f.take().unwrap()());
+                }
+            });
+            match claimed {
+                Ok(()) => break,
+                // Another thread is still running its initializer; `call_once_with` only makes
+                // a single attempt and doesn't wait, so spin until it finishes.
+                Err(_) if !self.call_once.was_called() => core::hint::spin_loop(),
+                Err(_) => break,
+            }
+        }
+        // The cell is now initialized, either by this call or by the thread that won the race.
+        self.get().unwrap()
+    }
+}
+
+impl<T> Default for CallOnceCell<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for CallOnceCell<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.get() {
+            Some(value) => f.debug_tuple("CallOnceCell").field(value).finish(),
+            None => f.write_str("CallOnceCell(<uninit>)"),
+        }
+    }
+}
+
+impl<T> Drop for CallOnceCell<T> {
+    fn drop(&mut self) {
+        if self.call_once.was_called() {
+            // SAFETY: the value was initialized, and we have exclusive access to it through
+            // `&mut self`.
+            unsafe {
+                self.value.get_mut().assume_init_drop();
+            }
+        }
+    }
+}
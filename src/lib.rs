@@ -11,16 +11,34 @@
 
 #![no_std]
 
+mod cell;
+
 use core::fmt;
-use core::sync::atomic::{AtomicBool, Ordering};
+use core::sync::atomic::{AtomicU8, Ordering};
+
+pub use cell::CallOnceCell;
+
+/// Not yet called.
+const INCOMPLETE: u8 = 0;
+/// Currently being called by some thread.
+const RUNNING: u8 = 1;
+/// Called successfully.
+const COMPLETE: u8 = 2;
+/// The closure passed to [`CallOnce::call_once_force`] poisoned this `CallOnce` by calling
+/// [`CallOnce::poison`].
+const POISONED: u8 = 3;
 
 /// A type that can only be called successfully _once_.
 ///
-/// This is a simple wrapper around an [`AtomicBool`] with a more descriptive API.
+/// This is a simple wrapper around an [`AtomicU8`] with a more descriptive API.
 ///
-/// <div class="warning">
-/// While <code>CallOnce</code> is synchronized and thread-safe, it does not synchronize other memory accesses.
-/// </div>
+/// `CallOnce` establishes a happens-before relationship between the winning call to
+/// [`call_once`](CallOnce::call_once) (or [`call_once_with`](CallOnce::call_once_with) or
+/// [`call_once_force`](CallOnce::call_once_force)) and any later call to
+/// [`was_called`](CallOnce::was_called) that returns `true`. This means memory writes performed
+/// by the winning thread are reliably observable by other threads after `was_called` returns
+/// `true`. An `Err` from one of the calling methods does not synchronize with the winning call:
+/// it is also returned while the winning call is still running.
 ///
 /// # Examples
 ///
@@ -32,9 +50,16 @@ use core::sync::atomic::{AtomicBool, Ordering};
 /// assert!(CALL_ONCE.call_once().is_ok());
 /// assert!(CALL_ONCE.call_once().is_err());
 /// ```
-#[derive(Default, Debug)]
+#[derive(Debug)]
 pub struct CallOnce {
-    called: AtomicBool,
+    state: AtomicU8,
+}
+
+impl Default for CallOnce {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl CallOnce {
@@ -50,7 +75,7 @@ impl CallOnce {
     #[inline]
     pub const fn new() -> Self {
         Self {
-            called: AtomicBool::new(false),
+            state: AtomicU8::new(INCOMPLETE),
         }
     }
 
@@ -71,13 +96,14 @@ impl CallOnce {
     /// ```
     #[inline]
     pub fn call_once(&self) -> Result<(), CallOnceError> {
-        self.called
-            .compare_exchange(false, true, Ordering::Relaxed, Ordering::Relaxed)
+        self.state
+            .compare_exchange(INCOMPLETE, COMPLETE, Ordering::Release, Ordering::Relaxed)
             .map(drop)
             .map_err(|_| CallOnceError)
     }
 
-    /// Returns `true` if `call_once` has been called.
+    /// Returns `true` if `call_once`, `call_once_with` or `call_once_force` has completed
+    /// successfully.
     ///
     /// # Examples
     ///
@@ -92,16 +118,146 @@ impl CallOnce {
     /// ```
     #[inline]
     pub fn was_called(&self) -> bool {
-        self.called.load(Ordering::Relaxed)
+        self.state.load(Ordering::Acquire) == COMPLETE
+    }
+
+    /// Calls `f` if this is the first call, returning its result.
+    ///
+    /// Only the first call runs `f` and returns `Ok` with its result.
+    /// All subsequent calls return `Err` without running `f`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use call_once::CallOnce;
+    ///
+    /// let call_once = CallOnce::new();
+    ///
+    /// assert_eq!(call_once.call_once_with(|| 1), Ok(1));
+    /// assert!(call_once.call_once_with(|| 2).is_err());
+    /// ```
+    #[inline]
+    pub fn call_once_with<T, F>(&self, f: F) -> Result<T, CallOnceError>
+    where
+        F: FnOnce() -> T,
+    {
+        self.state
+            .compare_exchange(INCOMPLETE, RUNNING, Ordering::Acquire, Ordering::Relaxed)
+            .map_err(|_| CallOnceError)?;
+        let value = f();
+        self.state.store(COMPLETE, Ordering::Release);
+        Ok(value)
+    }
+
+    /// Calls `f` if this is the first call or if this `CallOnce` was [poisoned](CallOnce::poison),
+    /// passing an [`OnceState`] that reports whether a previous call left it poisoned.
+    ///
+    /// This is the opt-in, recoverable counterpart to [`call_once_with`](CallOnce::call_once_with):
+    /// since panics cannot be caught in `no_std`, `f` itself is responsible for calling
+    /// [`poison`](CallOnce::poison) on a fallible path so that a later `call_once_force` can
+    /// retry instead of being permanently locked out.
+    ///
+    /// # Examples
+    ///
+    /// Poisoning from within the closure, on a fallible path, leaves the `CallOnce` poisoned
+    /// instead of completed, so a later call can retry:
+    ///
+    /// ```
+    /// use call_once::CallOnce;
+    ///
+    /// let call_once = CallOnce::new();
+    ///
+    /// let result = call_once.call_once_force(|state| {
+    ///     assert!(!state.poisoned());
+    ///     call_once.poison();
+    /// });
+    /// assert!(result.is_ok());
+    /// assert!(!call_once.was_called());
+    ///
+    /// let result = call_once.call_once_force(|state| {
+    ///     assert!(state.poisoned());
+    /// });
+    /// assert!(result.is_ok());
+    /// assert!(call_once.was_called());
+    /// ```
+    #[inline]
+    pub fn call_once_force<T>(&self, f: impl FnOnce(&OnceState) -> T) -> Result<T, CallOnceError> {
+        let poisoned = match self.state.compare_exchange(
+            INCOMPLETE,
+            RUNNING,
+            Ordering::Acquire,
+            Ordering::Relaxed,
+        ) {
+            Ok(_) => false,
+            Err(POISONED) => {
+                self.state
+                    .compare_exchange(POISONED, RUNNING, Ordering::Acquire, Ordering::Relaxed)
+                    .map_err(|_| CallOnceError)?;
+                true
+            }
+            Err(_) => return Err(CallOnceError),
+        };
+
+        let value = f(&OnceState { poisoned });
+        // `f` may have called `poison`, transitioning the state to `POISONED` on a fallible
+        // path; only mark the call complete if it is still `RUNNING`, so poisoning isn't
+        // stomped and a later `call_once_force` can retry.
+        let _ =
+            self.state
+                .compare_exchange(RUNNING, COMPLETE, Ordering::Release, Ordering::Relaxed);
+        Ok(value)
+    }
+
+    /// Poisons this `CallOnce`, so that a later [`call_once_force`](CallOnce::call_once_force)
+    /// observes [`OnceState::poisoned`] and is allowed to retry.
+    ///
+    /// This is meant to be called from within the closure passed to `call_once_force`, on a
+    /// fallible path that cannot complete the one-time initialization. Calling it at any other
+    /// time, i.e. while this `CallOnce` is not currently running such a closure, has no effect:
+    /// poisoning can only end a call that is actually in progress, so it can never retroactively
+    /// un-complete a `CallOnce` that already returned `Ok`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use call_once::CallOnce;
+    ///
+    /// let call_once = CallOnce::new();
+    ///
+    /// let result = call_once.call_once_force(|_state| call_once.poison());
+    /// assert!(result.is_ok());
+    /// assert!(!call_once.was_called());
+    /// ```
+    #[inline]
+    pub fn poison(&self) {
+        let _ =
+            self.state
+                .compare_exchange(RUNNING, POISONED, Ordering::Release, Ordering::Relaxed);
     }
 }
 
-/// The `CallOnceError` error indicates that [`CallOnce::call_once`] has been called more than once.
-#[derive(Debug)]
+/// The `CallOnceError` error indicates that a [`CallOnce`] has already been called, is currently
+/// being called, or is poisoned.
+#[derive(Debug, PartialEq, Eq)]
 pub struct CallOnceError;
 
 impl fmt::Display for CallOnceError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.write_str("call_once was executed more than once")
+        f.write_str("call_once was already called, is currently running, or is poisoned")
+    }
+}
+
+/// State yielded to the closure passed to [`CallOnce::call_once_force`].
+#[derive(Debug)]
+pub struct OnceState {
+    poisoned: bool,
+}
+
+impl OnceState {
+    /// Returns `true` if the associated [`CallOnce`] was poisoned prior to this call of
+    /// [`call_once_force`](CallOnce::call_once_force).
+    #[inline]
+    pub fn poisoned(&self) -> bool {
+        self.poisoned
     }
 }
@@ -0,0 +1,90 @@
+//! Cross-thread tests for the happens-before and contention guarantees documented on
+//! [`CallOnce`]. These need real threads, so they live here rather than in a doctest.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+use call_once::{CallOnce, CallOnceCell};
+
+const THREADS: usize = 8;
+
+#[test]
+fn call_once_with_runs_exactly_once_under_contention() {
+    let call_once = Arc::new(CallOnce::new());
+    let runs = Arc::new(AtomicUsize::new(0));
+
+    let handles: Vec<_> = (0..THREADS)
+        .map(|_| {
+            let call_once = Arc::clone(&call_once);
+            let runs = Arc::clone(&runs);
+            thread::spawn(move || call_once.call_once_with(|| runs.fetch_add(1, Ordering::SeqCst)))
+        })
+        .collect();
+
+    let oks = handles
+        .into_iter()
+        .map(|handle| handle.join().unwrap())
+        .filter(Result::is_ok)
+        .count();
+
+    assert_eq!(oks, 1);
+    assert_eq!(runs.load(Ordering::SeqCst), 1);
+    assert!(call_once.was_called());
+}
+
+#[test]
+fn call_once_force_runs_exactly_once_under_contention() {
+    let call_once = Arc::new(CallOnce::new());
+    let runs = Arc::new(AtomicUsize::new(0));
+
+    let handles: Vec<_> = (0..THREADS)
+        .map(|_| {
+            let call_once = Arc::clone(&call_once);
+            let runs = Arc::clone(&runs);
+            thread::spawn(move || {
+                call_once.call_once_force(|state| {
+                    assert!(!state.poisoned());
+                    runs.fetch_add(1, Ordering::SeqCst)
+                })
+            })
+        })
+        .collect();
+
+    let oks = handles
+        .into_iter()
+        .map(|handle| handle.join().unwrap())
+        .filter(Result::is_ok)
+        .count();
+
+    assert_eq!(oks, 1);
+    assert_eq!(runs.load(Ordering::SeqCst), 1);
+    assert!(call_once.was_called());
+}
+
+#[test]
+fn get_or_init_converges_to_a_single_value_under_contention() {
+    let cell = Arc::new(CallOnceCell::new());
+    let inits = Arc::new(AtomicUsize::new(0));
+
+    let handles: Vec<_> = (0..THREADS)
+        .map(|thread_id| {
+            let cell = Arc::clone(&cell);
+            let inits = Arc::clone(&inits);
+            thread::spawn(move || {
+                *cell.get_or_init(|| {
+                    inits.fetch_add(1, Ordering::SeqCst);
+                    thread_id
+                })
+            })
+        })
+        .collect();
+
+    let values: Vec<_> = handles
+        .into_iter()
+        .map(|handle| handle.join().unwrap())
+        .collect();
+
+    assert_eq!(inits.load(Ordering::SeqCst), 1);
+    assert!(values.iter().all(|&value| value == values[0]));
+}